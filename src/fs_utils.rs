@@ -2,11 +2,15 @@ use crate::{
     compressor::Compressor,
     constants::{GIT_BASE_DIR, GIT_OBJECTS_DIR},
     git_object::{
-        tree_line::{TREE_LINE_MODE_FILE, TREE_LINE_MODE_FOLDER},
+        tree_line::{
+            TREE_LINE_MODE_EXECUTABLE, TREE_LINE_MODE_FILE, TREE_LINE_MODE_FOLDER,
+            TREE_LINE_MODE_SYMLINK,
+        },
         GitObject, GIT_OBJECT_TYPE_BLOB, GIT_OBJECT_TYPE_TREE,
     },
     hasher::create_hash,
 };
+use std::os::unix::{ffi::OsStringExt, fs::PermissionsExt};
 use std::{fs, vec};
 
 pub struct FsUtils {}
@@ -74,8 +78,12 @@ impl FsUtils {
                 continue;
             };
 
-            // Directory
-            if entry_path.is_dir() {
+            // Inspect the entry itself, never the symlink target.
+            let metadata = fs::symlink_metadata(&entry_path)?;
+            let is_symlink = metadata.file_type().is_symlink();
+
+            // Directory (a symlink to a directory is a blob, not a subtree)
+            if !is_symlink && entry_path.is_dir() {
                 let Ok(entry_path_string) = entry_path.clone().into_os_string().into_string()
                 else {
                     continue;
@@ -87,10 +95,23 @@ impl FsUtils {
                 continue;
             }
 
-            // File
-            let header = format!("{} {}\0", TREE_LINE_MODE_FILE, file_name_string);
+            // File or symlink
+            let mode = if is_symlink {
+                TREE_LINE_MODE_SYMLINK
+            } else if metadata.permissions().mode() & 0o100 != 0 {
+                TREE_LINE_MODE_EXECUTABLE
+            } else {
+                TREE_LINE_MODE_FILE
+            };
+            let header = format!("{} {}\0", mode, file_name_string);
             tree_bytes.extend(header.bytes());
-            let file_bytes = fs::read(entry_path)?;
+
+            // A symlink is stored as a blob holding its target path.
+            let file_bytes = if is_symlink {
+                fs::read_link(&entry_path)?.into_os_string().into_vec()
+            } else {
+                fs::read(&entry_path)?
+            };
             let hash = Self::write_object(GIT_OBJECT_TYPE_BLOB, &file_bytes)?;
             tree_bytes.extend(&hash);
         }
@@ -101,7 +122,7 @@ impl FsUtils {
         Ok(hash)
     }
 
-    fn write_object(object_type: &str, content_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+    pub fn write_object(object_type: &str, content_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
         let header = format!("{object_type} {}\0", content_bytes.len());
         let object_bytes = [header.as_bytes(), &content_bytes].concat();
 
@@ -123,3 +144,50 @@ impl FsUtils {
         Ok(hash)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::GIT_OBJECTS_DIR;
+    use crate::git_object::tree_line::{
+        TreeLines, TREE_LINE_MODE_EXECUTABLE, TREE_LINE_MODE_FILE, TREE_LINE_MODE_SYMLINK,
+    };
+    use std::io::Read;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn write_tree_detects_file_executable_and_symlink_modes() {
+        // Isolated working tree so object writes don't touch the real repo.
+        let root = std::env::temp_dir().join(format!("git-write-tree-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        std::env::set_current_dir(&root).unwrap();
+        fs::create_dir_all(GIT_OBJECTS_DIR).unwrap();
+
+        // A regular file, an owner-executable file, and a symlink to a directory.
+        fs::create_dir("work").unwrap();
+        fs::write("work/regular.txt", b"hello").unwrap();
+        fs::write("work/run.sh", b"#!/bin/sh\n").unwrap();
+        fs::set_permissions("work/run.sh", fs::Permissions::from_mode(0o755)).unwrap();
+        fs::create_dir("work/target").unwrap();
+        symlink("target", "work/link").unwrap();
+
+        let hash = hex::encode(FsUtils::write_tree("work".to_string()).unwrap());
+
+        // Read the written tree back and collect the mode of each entry.
+        let bytes = FsUtils::read_bytes_for_hash(&hash).unwrap();
+        let mut decoder = flate2::read::ZlibDecoder::new(bytes.as_slice());
+        let mut decompressed = vec![];
+        decoder.read_to_end(&mut decompressed).unwrap();
+        let null_index = decompressed.iter().position(|&b| b == b'\0').unwrap();
+        let lines = TreeLines::from_bytes(&decompressed[(null_index + 1)..]).unwrap();
+
+        let mode_for =
+            |path: &str| lines.0.iter().find(|l| l.path == path).map(|l| l.mode.clone());
+        assert_eq!(mode_for("regular.txt").as_deref(), Some(TREE_LINE_MODE_FILE));
+        assert_eq!(mode_for("run.sh").as_deref(), Some(TREE_LINE_MODE_EXECUTABLE));
+        assert_eq!(mode_for("link").as_deref(), Some(TREE_LINE_MODE_SYMLINK));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
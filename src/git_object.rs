@@ -1,4 +1,8 @@
+pub mod commit;
+pub mod tree_line;
+
 use crate::constants::GIT_OBJECTS_DIR;
+use crate::git_object::commit::Commit;
 use flate2::{read::ZlibDecoder, write::ZlibEncoder};
 use sha1::{Digest, Sha1};
 use std::{
@@ -8,10 +12,12 @@ use std::{
 
 pub const GIT_OBJECT_TYPE_BLOB: &str = "blob";
 pub const GIT_OBJECT_TYPE_TREE: &str = "tree";
+pub const GIT_OBJECT_TYPE_COMMIT: &str = "commit";
 
 pub enum GitObject {
     Blob(String),
     Tree(String),
+    Commit(Commit),
 }
 
 impl GitObject {
@@ -19,6 +25,7 @@ impl GitObject {
         match self {
             GitObject::Blob(_) => GIT_OBJECT_TYPE_BLOB.to_string(),
             GitObject::Tree(_) => GIT_OBJECT_TYPE_TREE.to_string(),
+            GitObject::Commit(_) => GIT_OBJECT_TYPE_COMMIT.to_string(),
         }
     }
 }
@@ -29,6 +36,9 @@ impl GitObject {
         match object_type {
             GIT_OBJECT_TYPE_BLOB => Ok(GitObject::Blob(content_string)),
             GIT_OBJECT_TYPE_TREE => Ok(GitObject::Tree(content_string)),
+            GIT_OBJECT_TYPE_COMMIT => {
+                Ok(GitObject::Commit(Commit::from_content_string(&content_string)?))
+            }
             _ => Err(anyhow::anyhow!(format!(
                 "Invalid object type {}",
                 object_type
@@ -92,8 +102,9 @@ impl GitObject {
     fn to_raw(&self) -> anyhow::Result<(String, Vec<u8>)> {
         let object_type = self.object_type();
         let content_string = match self {
-            GitObject::Blob(content_string) => content_string,
-            GitObject::Tree(content_string) => content_string,
+            GitObject::Blob(content_string) => content_string.clone(),
+            GitObject::Tree(content_string) => content_string.clone(),
+            GitObject::Commit(commit) => commit.to_content_string(),
         };
         let header = format!("{object_type} {}\0", content_string.len());
         let content = [header.as_bytes(), content_string.as_bytes()].concat();
@@ -0,0 +1,121 @@
+#[derive(Debug, Clone)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+    pub timestamp: i64,
+    pub timezone: String,
+}
+
+impl Identity {
+    pub fn new(name: &str, email: &str, timestamp: i64, timezone: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            email: email.to_string(),
+            timestamp,
+            timezone: timezone.to_string(),
+        }
+    }
+
+    fn from_line(line: &str) -> anyhow::Result<Self> {
+        // Name <email> <timestamp> <timezone>
+        let Some((name, line)) = line.split_once(" <") else {
+            return Err(anyhow::anyhow!("Invalid identity line"));
+        };
+        let Some((email, line)) = line.split_once("> ") else {
+            return Err(anyhow::anyhow!("Invalid identity line"));
+        };
+        let Some((timestamp, timezone)) = line.split_once(' ') else {
+            return Err(anyhow::anyhow!("Invalid identity line"));
+        };
+        let timestamp = timestamp.parse::<i64>()?;
+        Ok(Identity::new(name, email, timestamp, timezone))
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{} <{}> {} {}",
+            self.name, self.email, self.timestamp, self.timezone
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Commit {
+    pub tree: String,
+    pub parents: Vec<String>,
+    pub author: Identity,
+    pub committer: Identity,
+    pub message: String,
+}
+
+impl Commit {
+    pub fn new(
+        tree: &str,
+        parents: &[String],
+        author: Identity,
+        committer: Identity,
+        message: &str,
+    ) -> Self {
+        Self {
+            tree: tree.to_string(),
+            parents: parents.to_vec(),
+            author,
+            committer,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn from_content_string(content_string: &str) -> anyhow::Result<Self> {
+        let Some((header, message)) = content_string.split_once("\n\n") else {
+            return Err(anyhow::anyhow!("Invalid commit object"));
+        };
+
+        let mut tree: Option<String> = None;
+        let mut parents: Vec<String> = vec![];
+        let mut author: Option<Identity> = None;
+        let mut committer: Option<Identity> = None;
+
+        for line in header.lines() {
+            let Some((key, value)) = line.split_once(' ') else {
+                continue;
+            };
+            match key {
+                "tree" => tree = Some(value.to_string()),
+                "parent" => parents.push(value.to_string()),
+                "author" => author = Some(Identity::from_line(value)?),
+                "committer" => committer = Some(Identity::from_line(value)?),
+                _ => {}
+            }
+        }
+
+        let Some(tree) = tree else {
+            return Err(anyhow::anyhow!("Commit object is missing a tree"));
+        };
+        let Some(author) = author else {
+            return Err(anyhow::anyhow!("Commit object is missing an author"));
+        };
+        let Some(committer) = committer else {
+            return Err(anyhow::anyhow!("Commit object is missing a committer"));
+        };
+
+        Ok(Commit::new(
+            tree.as_str(),
+            parents.as_slice(),
+            author,
+            committer,
+            message,
+        ))
+    }
+
+    pub fn to_content_string(&self) -> String {
+        let mut content_string = format!("tree {}\n", self.tree);
+        for parent in &self.parents {
+            content_string.push_str(&format!("parent {}\n", parent));
+        }
+        content_string.push_str(&format!("author {}\n", self.author.to_line()));
+        content_string.push_str(&format!("committer {}\n", self.committer.to_line()));
+        content_string.push('\n');
+        content_string.push_str(&self.message);
+        content_string
+    }
+}
@@ -1,5 +1,10 @@
 const HASH_BYTES_LENGTH: usize = 20;
 
+pub const TREE_LINE_MODE_FILE: &str = "100644";
+pub const TREE_LINE_MODE_EXECUTABLE: &str = "100755";
+pub const TREE_LINE_MODE_SYMLINK: &str = "120000";
+pub const TREE_LINE_MODE_FOLDER: &str = "40000";
+
 #[derive(Debug, Clone)]
 pub struct TreeLine {
     pub mode: String,
@@ -61,16 +66,69 @@ impl TreeLines {
         Ok(TreeLines::new(lines.as_slice()))
     }
 
-    pub fn to_bytes(&self) -> Vec<u8> {
-        todo!();
-        // let mut bytes: Vec<u8> = vec![];
-        // for line in self.0.clone() {
-        //     bytes.extend(line.mode.as_bytes());
-        //     bytes.push(b' ');
-        //     bytes.extend(line.path.as_bytes());
-        //     bytes.push(b'\0');
-        //     bytes.extend(hex::decode(line.hash).unwrap());
-        // }
-        // bytes
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes: Vec<u8> = vec![];
+        for line in &self.0 {
+            bytes.extend(line.mode.as_bytes());
+            bytes.push(b' ');
+            bytes.extend(line.path.as_bytes());
+            bytes.push(b'\0');
+            bytes.extend(hex::decode(&line.hash)?);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{read::ZlibDecoder, write::ZlibEncoder};
+    use sha1::{Digest, Sha1};
+    use std::fs;
+    use std::io::{Read, Write};
+
+    /// Hashes an object and returns its id alongside the zlib-compressed bytes
+    /// exactly as a loose object is stored.
+    fn store_object(bytes: &[u8]) -> (String, Vec<u8>) {
+        let mut hasher = Sha1::new();
+        hasher.update(bytes);
+        let hash = hex::encode(hasher.finalize());
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(bytes).unwrap();
+        (hash, encoder.finish().unwrap())
+    }
+
+    #[test]
+    fn reads_on_disk_tree_and_round_trips_to_same_object_id() {
+        // A real tree object: `tree <len>\0` followed by sorted entries.
+        let mut entries: Vec<u8> = vec![];
+        entries.extend(b"40000 dir\0");
+        entries.extend(hex::decode("89abcdef0123456789abcdef0123456789abcdef").unwrap());
+        entries.extend(b"100644 file.txt\0");
+        entries.extend(hex::decode("0123456789abcdef0123456789abcdef01234567").unwrap());
+        let object = [format!("tree {}\0", entries.len()).as_bytes(), &entries].concat();
+
+        // Write it to disk as a loose, zlib-compressed object.
+        let (object_id, compressed) = store_object(&object);
+        let path = std::env::temp_dir().join(format!("git-tree-round-trip-{}", object_id));
+        fs::write(&path, &compressed).unwrap();
+
+        // Read it back and strip the `tree <len>\0` header.
+        let stored = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let mut decoder = ZlibDecoder::new(stored.as_slice());
+        let mut decompressed = vec![];
+        decoder.read_to_end(&mut decompressed).unwrap();
+        let null_index = decompressed.iter().position(|&b| b == b'\0').unwrap();
+        let content = &decompressed[(null_index + 1)..];
+
+        // Re-serialize through TreeLines and re-hash; the id must be identical.
+        let reserialized = TreeLines::from_bytes(content).unwrap().to_bytes().unwrap();
+        let reobject =
+            [format!("tree {}\0", reserialized.len()).as_bytes(), &reserialized].concat();
+        let (rehashed_id, _) = store_object(&reobject);
+
+        assert_eq!(rehashed_id, object_id);
     }
 }
\ No newline at end of file
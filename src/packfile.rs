@@ -0,0 +1,446 @@
+use crate::fs_utils::FsUtils;
+use crate::git_object::{
+    tree_line::TreeLines, GitObject, GIT_OBJECT_TYPE_BLOB, GIT_OBJECT_TYPE_COMMIT,
+    GIT_OBJECT_TYPE_TREE,
+};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use sha1::{Digest, Sha1};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+
+const PACK_SIGNATURE: &[u8] = b"PACK";
+const PACK_TRAILER_LENGTH: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackObjectType {
+    Commit,
+    Tree,
+    Blob,
+    Tag,
+    OfsDelta,
+    RefDelta,
+}
+
+impl PackObjectType {
+    fn from_id(id: u8) -> anyhow::Result<Self> {
+        match id {
+            1 => Ok(PackObjectType::Commit),
+            2 => Ok(PackObjectType::Tree),
+            3 => Ok(PackObjectType::Blob),
+            4 => Ok(PackObjectType::Tag),
+            6 => Ok(PackObjectType::OfsDelta),
+            7 => Ok(PackObjectType::RefDelta),
+            _ => Err(anyhow::anyhow!("Invalid pack object type {}", id)),
+        }
+    }
+
+    fn id(&self) -> u8 {
+        match self {
+            PackObjectType::Commit => 1,
+            PackObjectType::Tree => 2,
+            PackObjectType::Blob => 3,
+            PackObjectType::Tag => 4,
+            PackObjectType::OfsDelta => 6,
+            PackObjectType::RefDelta => 7,
+        }
+    }
+
+    fn from_git_object_type(object_type: &str) -> anyhow::Result<Self> {
+        match object_type {
+            GIT_OBJECT_TYPE_COMMIT => Ok(PackObjectType::Commit),
+            GIT_OBJECT_TYPE_TREE => Ok(PackObjectType::Tree),
+            GIT_OBJECT_TYPE_BLOB => Ok(PackObjectType::Blob),
+            "tag" => Ok(PackObjectType::Tag),
+            _ => Err(anyhow::anyhow!("Unpackable object type {}", object_type)),
+        }
+    }
+
+    pub fn is_delta(&self) -> bool {
+        matches!(self, PackObjectType::OfsDelta | PackObjectType::RefDelta)
+    }
+
+    /// Maps a non-delta pack type to its loose-object type string.
+    pub fn git_object_type(&self) -> anyhow::Result<&'static str> {
+        match self {
+            PackObjectType::Commit => Ok(GIT_OBJECT_TYPE_COMMIT),
+            PackObjectType::Tree => Ok(GIT_OBJECT_TYPE_TREE),
+            PackObjectType::Blob => Ok(GIT_OBJECT_TYPE_BLOB),
+            PackObjectType::Tag => Ok("tag"),
+            _ => Err(anyhow::anyhow!("Delta objects have no base type")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PackObject {
+    pub object_type: PackObjectType,
+    /// Inflated payload. For delta objects this is the raw delta instruction stream.
+    pub data: Vec<u8>,
+    /// Absolute offset of the base object, for `ofs-delta`.
+    pub base_offset: Option<usize>,
+    /// Base object id, for `ref-delta`.
+    pub base_hash: Option<String>,
+}
+
+pub struct PackFile {}
+
+impl PackFile {
+    /// Decodes a packfile into its objects, keyed by their offset in the pack so
+    /// that `ofs-delta` bases can be resolved later. Non-delta objects are written
+    /// straight to the object store; deltas are returned unresolved.
+    pub fn decode(bytes: &[u8]) -> anyhow::Result<HashMap<usize, PackObject>> {
+        if bytes.len() < 12 + PACK_TRAILER_LENGTH {
+            return Err(anyhow::anyhow!("Packfile is too short"));
+        }
+
+        // Verify the trailing SHA-1 against every byte that precedes it.
+        let (body, trailer) = bytes.split_at(bytes.len() - PACK_TRAILER_LENGTH);
+        let mut hasher = Sha1::new();
+        hasher.update(body);
+        if hasher.finalize().as_slice() != trailer {
+            return Err(anyhow::anyhow!("Packfile checksum mismatch"));
+        }
+
+        // Header: "PACK", 4-byte version, 4-byte object count.
+        if &bytes[..4] != PACK_SIGNATURE {
+            return Err(anyhow::anyhow!("Invalid packfile signature"));
+        }
+        let object_count = u32::from_be_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+
+        let mut objects: HashMap<usize, PackObject> = HashMap::new();
+        let mut cursor = 12;
+
+        for _ in 0..object_count {
+            let object_offset = cursor;
+
+            // Variable-length type/size header.
+            let (object_type, _size, consumed) = Self::read_type_and_size(&bytes[cursor..])?;
+            cursor += consumed;
+
+            let mut base_offset: Option<usize> = None;
+            let mut base_hash: Option<String> = None;
+
+            match object_type {
+                PackObjectType::OfsDelta => {
+                    let (negative_offset, consumed) = Self::read_offset(&bytes[cursor..])?;
+                    cursor += consumed;
+                    base_offset = Some(object_offset - negative_offset);
+                }
+                PackObjectType::RefDelta => {
+                    let hash = &bytes[cursor..cursor + PACK_TRAILER_LENGTH];
+                    cursor += PACK_TRAILER_LENGTH;
+                    base_hash = Some(hex::encode(hash));
+                }
+                _ => {}
+            }
+
+            // Inflate the zlib payload; `total_in` tells us how many bytes it ate.
+            let mut decoder = ZlibDecoder::new(&bytes[cursor..body.len()]);
+            let mut data = vec![];
+            decoder.read_to_end(&mut data)?;
+            cursor += decoder.total_in() as usize;
+
+            // Non-delta objects can be stored immediately.
+            if !object_type.is_delta() {
+                FsUtils::write_object(object_type.git_object_type()?, &data)?;
+            }
+
+            objects.insert(
+                object_offset,
+                PackObject {
+                    object_type,
+                    data,
+                    base_offset,
+                    base_hash,
+                },
+            );
+        }
+
+        Ok(objects)
+    }
+
+    fn read_type_and_size(bytes: &[u8]) -> anyhow::Result<(PackObjectType, usize, usize)> {
+        let mut index = 0;
+        let first = bytes[index];
+        index += 1;
+
+        let object_type = PackObjectType::from_id((first >> 4) & 0b111)?;
+        let mut size = (first & 0b1111) as usize;
+        let mut shift = 4;
+
+        let mut byte = first;
+        while byte & 0b1000_0000 != 0 {
+            byte = bytes[index];
+            index += 1;
+            size |= ((byte & 0b0111_1111) as usize) << shift;
+            shift += 7;
+        }
+
+        Ok((object_type, size, index))
+    }
+
+    fn read_offset(bytes: &[u8]) -> anyhow::Result<(usize, usize)> {
+        let mut index = 0;
+        let mut byte = bytes[index];
+        index += 1;
+        let mut offset = (byte & 0b0111_1111) as usize;
+
+        while byte & 0b1000_0000 != 0 {
+            byte = bytes[index];
+            index += 1;
+            offset = ((offset + 1) << 7) | (byte & 0b0111_1111) as usize;
+        }
+
+        Ok((offset, index))
+    }
+
+    /// Resolves every delta object against its base and writes the reconstructed
+    /// objects to the store. Non-delta objects were already written during `decode`.
+    pub fn resolve_and_write(objects: &HashMap<usize, PackObject>) -> anyhow::Result<()> {
+        // Index every resolved object by its id so `ref-delta` bases that are
+        // themselves deltas in this pack can be found without hitting the store.
+        // A `ref-delta` base may appear after its dependents, so keep sweeping
+        // the unresolved set until the index stops growing.
+        let mut resolved_by_hash: HashMap<String, (String, Vec<u8>)> = HashMap::new();
+        let mut pending: Vec<usize> = objects.keys().copied().collect();
+        pending.sort_unstable();
+
+        loop {
+            let mut progressed = false;
+            let mut still_pending: Vec<usize> = vec![];
+
+            for &offset in &pending {
+                match Self::resolve(offset, objects, &resolved_by_hash) {
+                    Ok((object_type, content)) => {
+                        // Non-delta objects were already written during `decode`.
+                        if objects[&offset].object_type.is_delta() {
+                            FsUtils::write_object(&object_type, &content)?;
+                        }
+                        let hash = Self::hash_object(&object_type, &content);
+                        resolved_by_hash.insert(hash, (object_type, content));
+                        progressed = true;
+                    }
+                    Err(_) => still_pending.push(offset),
+                }
+            }
+
+            if still_pending.is_empty() {
+                break;
+            }
+            if !progressed {
+                return Err(anyhow::anyhow!("Unresolvable delta chain in packfile"));
+            }
+            pending = still_pending;
+        }
+
+        Ok(())
+    }
+
+    /// Reconstructs the object at `offset`, following delta bases recursively.
+    /// `ofs-delta` bases are located in the pack by offset; `ref-delta` bases
+    /// are looked up by id in `resolved_by_hash`, falling back to the store.
+    fn resolve(
+        offset: usize,
+        objects: &HashMap<usize, PackObject>,
+        resolved_by_hash: &HashMap<String, (String, Vec<u8>)>,
+    ) -> anyhow::Result<(String, Vec<u8>)> {
+        let Some(object) = objects.get(&offset) else {
+            return Err(anyhow::anyhow!("Missing base object at offset {}", offset));
+        };
+
+        if !object.object_type.is_delta() {
+            return Ok((object.object_type.git_object_type()?.to_string(), object.data.clone()));
+        }
+
+        let (base_type, base_content) = match object.object_type {
+            PackObjectType::OfsDelta => {
+                let Some(base_offset) = object.base_offset else {
+                    return Err(anyhow::anyhow!("ofs-delta without base offset"));
+                };
+                Self::resolve(base_offset, objects, resolved_by_hash)?
+            }
+            PackObjectType::RefDelta => {
+                let Some(base_hash) = &object.base_hash else {
+                    return Err(anyhow::anyhow!("ref-delta without base hash"));
+                };
+                match resolved_by_hash.get(base_hash) {
+                    Some(base) => base.clone(),
+                    None => Self::read_base_from_store(base_hash)?,
+                }
+            }
+            _ => unreachable!(),
+        };
+
+        let content = Self::apply_delta(&base_content, &object.data)?;
+        Ok((base_type, content))
+    }
+
+    /// Computes the loose-object id (`<type> <len>\0<content>` hashed with SHA-1).
+    fn hash_object(object_type: &str, content: &[u8]) -> String {
+        let header = format!("{} {}\0", object_type, content.len());
+        let mut hasher = Sha1::new();
+        hasher.update(header.as_bytes());
+        hasher.update(content);
+        hex::encode(hasher.finalize())
+    }
+
+    fn read_base_from_store(hash: &str) -> anyhow::Result<(String, Vec<u8>)> {
+        let compressed = FsUtils::read_bytes_for_hash(hash)?;
+        let mut decoder = ZlibDecoder::new(compressed.as_slice());
+        let mut decompressed = vec![];
+        decoder.read_to_end(&mut decompressed)?;
+
+        let Some(null_index) = decompressed.iter().position(|&b| b == b'\0') else {
+            return Err(anyhow::anyhow!("Invalid base object header"));
+        };
+        let header = String::from_utf8_lossy(&decompressed[..null_index]).to_string();
+        let Some((object_type, _)) = header.split_once(' ') else {
+            return Err(anyhow::anyhow!("Invalid base object header"));
+        };
+        let content = decompressed[(null_index + 1)..].to_vec();
+        Ok((object_type.to_string(), content))
+    }
+
+    /// Reconstructs an object by applying a Git delta stream against a base buffer.
+    pub fn apply_delta(base: &[u8], delta: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut index = 0;
+        let (_source_size, consumed) = Self::read_varint(&delta[index..]);
+        index += consumed;
+        let (target_size, consumed) = Self::read_varint(&delta[index..]);
+        index += consumed;
+
+        let mut output = Vec::with_capacity(target_size);
+
+        while index < delta.len() {
+            let instruction = delta[index];
+            index += 1;
+
+            if instruction & 0b1000_0000 != 0 {
+                // Copy: the low 7 bits select which offset/size bytes follow.
+                let mut copy_offset = 0usize;
+                for i in 0..4 {
+                    if instruction & (1 << i) != 0 {
+                        copy_offset |= (delta[index] as usize) << (8 * i);
+                        index += 1;
+                    }
+                }
+                let mut copy_size = 0usize;
+                for i in 0..3 {
+                    if instruction & (1 << (4 + i)) != 0 {
+                        copy_size |= (delta[index] as usize) << (8 * i);
+                        index += 1;
+                    }
+                }
+                if copy_size == 0 {
+                    copy_size = 0x10000;
+                }
+                output.extend_from_slice(&base[copy_offset..copy_offset + copy_size]);
+            } else if instruction != 0 {
+                // Insert: append the next `instruction` bytes verbatim.
+                let insert_size = instruction as usize;
+                output.extend_from_slice(&delta[index..index + insert_size]);
+                index += insert_size;
+            } else {
+                return Err(anyhow::anyhow!("Invalid delta instruction"));
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn read_varint(bytes: &[u8]) -> (usize, usize) {
+        let mut result = 0usize;
+        let mut shift = 0;
+        let mut index = 0;
+
+        loop {
+            let byte = bytes[index];
+            index += 1;
+            result |= ((byte & 0b0111_1111) as usize) << shift;
+            shift += 7;
+            if byte & 0b1000_0000 == 0 {
+                break;
+            }
+        }
+
+        (result, index)
+    }
+
+    /// Serializes every object reachable from `root_hash` into a version-2
+    /// packfile. Objects are stored whole (no deltas) so it pairs with `decode`.
+    pub fn encode(root_hash: &str) -> anyhow::Result<Vec<u8>> {
+        let mut hashes: Vec<String> = vec![];
+        let mut seen: HashSet<String> = HashSet::new();
+        Self::collect_reachable(root_hash, &mut hashes, &mut seen)?;
+
+        let mut body: Vec<u8> = vec![];
+        body.extend_from_slice(PACK_SIGNATURE);
+        body.extend_from_slice(&2u32.to_be_bytes());
+        body.extend_from_slice(&(hashes.len() as u32).to_be_bytes());
+
+        for hash in &hashes {
+            let (object_type, content) = Self::read_base_from_store(hash)?;
+            let pack_type = PackObjectType::from_git_object_type(&object_type)?;
+            body.extend(Self::encode_type_and_size(pack_type, content.len()));
+
+            let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(&content)?;
+            body.extend(encoder.finish()?);
+        }
+
+        // Trailing SHA-1 over everything written so far.
+        let mut hasher = Sha1::new();
+        hasher.update(&body);
+        body.extend_from_slice(&hasher.finalize());
+
+        Ok(body)
+    }
+
+    /// Walks commit -> tree -> blob edges, collecting each object id exactly once.
+    fn collect_reachable(
+        hash: &str,
+        hashes: &mut Vec<String>,
+        seen: &mut HashSet<String>,
+    ) -> anyhow::Result<()> {
+        if !seen.insert(hash.to_string()) {
+            return Ok(());
+        }
+        hashes.push(hash.to_string());
+
+        match GitObject::from_hash(hash)? {
+            GitObject::Commit(commit) => {
+                Self::collect_reachable(&commit.tree, hashes, seen)?;
+                for parent in &commit.parents {
+                    Self::collect_reachable(parent, hashes, seen)?;
+                }
+            }
+            GitObject::Tree(_) => {
+                let (_, content) = Self::read_base_from_store(hash)?;
+                for line in &TreeLines::from_bytes(&content)?.0 {
+                    Self::collect_reachable(&line.hash, hashes, seen)?;
+                }
+            }
+            GitObject::Blob(_) => {}
+        }
+
+        Ok(())
+    }
+
+    fn encode_type_and_size(object_type: PackObjectType, size: usize) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+
+        // First byte: 3-bit type and the low 4 bits of the size.
+        let mut byte = (object_type.id() << 4) | (size & 0b1111) as u8;
+        let mut size = size >> 4;
+
+        while size > 0 {
+            bytes.push(byte | 0b1000_0000);
+            byte = (size & 0b0111_1111) as u8;
+            size >>= 7;
+        }
+        bytes.push(byte);
+
+        bytes
+    }
+}
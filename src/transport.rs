@@ -0,0 +1,151 @@
+use crate::packfile::PackFile;
+
+const SIDE_BAND_PACK_DATA: u8 = 1;
+const SIDE_BAND_PROGRESS: u8 = 2;
+const SIDE_BAND_ERROR: u8 = 3;
+
+#[derive(Debug, Clone)]
+pub struct RemoteRef {
+    pub hash: String,
+    pub name: String,
+}
+
+pub struct Transport {
+    base_url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl Transport {
+    pub fn new(url: &str) -> Self {
+        Self {
+            base_url: url.trim_end_matches('/').to_string(),
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+
+    /// Fetches every ref from the remote and stores the resulting objects,
+    /// returning the advertised refs so the caller can lay down HEAD and branches.
+    pub fn clone_repo(&self) -> anyhow::Result<Vec<RemoteRef>> {
+        let refs = self.discover_refs()?;
+        let pack = self.fetch_pack(&refs)?;
+        let objects = PackFile::decode(&pack)?;
+        PackFile::resolve_and_write(&objects)?;
+        Ok(refs)
+    }
+
+    /// GET `/info/refs?service=git-upload-pack` and parse the ref advertisement.
+    fn discover_refs(&self) -> anyhow::Result<Vec<RemoteRef>> {
+        let url = format!("{}/info/refs?service=git-upload-pack", self.base_url);
+        let body = self
+            .client
+            .get(&url)
+            .send()?
+            .error_for_status()?
+            .bytes()?;
+
+        let mut refs: Vec<RemoteRef> = vec![];
+        for line in Self::decode_pkt_lines(&body)? {
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches('\n');
+
+            // Skip the `# service=...` banner and the flush line before the refs.
+            if line.starts_with('#') || line.is_empty() {
+                continue;
+            }
+
+            // Each ref line is `<sha> <name>`, with capabilities after a NUL on the first.
+            let line = line.split('\0').next().unwrap_or(line);
+            let Some((hash, name)) = line.split_once(' ') else {
+                continue;
+            };
+            refs.push(RemoteRef {
+                hash: hash.to_string(),
+                name: name.to_string(),
+            });
+        }
+
+        Ok(refs)
+    }
+
+    /// POST the `want` negotiation and demultiplex the side-band packfile response.
+    fn fetch_pack(&self, refs: &[RemoteRef]) -> anyhow::Result<Vec<u8>> {
+        let url = format!("{}/git-upload-pack", self.base_url);
+
+        let mut request = String::new();
+        for (index, remote_ref) in refs.iter().enumerate() {
+            if index == 0 {
+                request.push_str(&Self::encode_pkt_line(&format!(
+                    "want {} multi_ack side-band-64k\n",
+                    remote_ref.hash
+                )));
+            } else {
+                request.push_str(&Self::encode_pkt_line(&format!("want {}\n", remote_ref.hash)));
+            }
+        }
+        request.push_str("0000");
+        request.push_str(&Self::encode_pkt_line("done\n"));
+
+        let body = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/x-git-upload-pack-request")
+            .body(request)
+            .send()?
+            .error_for_status()?
+            .bytes()?;
+
+        let mut pack: Vec<u8> = vec![];
+        for line in Self::decode_pkt_lines(&body)? {
+            let Some((band, data)) = line.split_first() else {
+                continue;
+            };
+            match *band {
+                SIDE_BAND_PACK_DATA => pack.extend_from_slice(data),
+                SIDE_BAND_PROGRESS => {}
+                SIDE_BAND_ERROR => {
+                    return Err(anyhow::anyhow!(
+                        "Remote error: {}",
+                        String::from_utf8_lossy(data)
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(pack)
+    }
+
+    fn encode_pkt_line(payload: &str) -> String {
+        format!("{:04x}{}", payload.len() + 4, payload)
+    }
+
+    /// Splits a pkt-line stream into payloads, dropping flush (`0000`) and
+    /// delimiter (`0001`) packets.
+    fn decode_pkt_lines(bytes: &[u8]) -> anyhow::Result<Vec<Vec<u8>>> {
+        let mut lines: Vec<Vec<u8>> = vec![];
+        let mut cursor = 0;
+
+        while cursor + 4 <= bytes.len() {
+            let length_hex = std::str::from_utf8(&bytes[cursor..cursor + 4])?;
+            let length = usize::from_str_radix(length_hex, 16)?;
+            cursor += 4;
+
+            // Flush (0000) and delimiter (0001) carry no payload.
+            if length == 0 || length == 1 {
+                continue;
+            }
+            if length < 4 {
+                return Err(anyhow::anyhow!("Invalid pkt-line length {}", length));
+            }
+
+            let payload_len = length - 4;
+            if cursor + payload_len > bytes.len() {
+                return Err(anyhow::anyhow!("Truncated pkt-line"));
+            }
+            lines.push(bytes[cursor..cursor + payload_len].to_vec());
+            cursor += payload_len;
+        }
+
+        Ok(lines)
+    }
+}